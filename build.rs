@@ -0,0 +1,38 @@
+//! Generates the embedded license corpus from the plain-text license bodies
+//! checked into `data/licenses/` (one `<SPDX-ID>.txt` file per license),
+//! so `src/licenses/store.rs` never `include_bytes!`s an artifact that
+//! isn't actually in the tree.
+
+use std::path::Path;
+
+fn main() {
+    let licenses_dir = Path::new("data/licenses");
+    println!("cargo:rerun-if-changed={}", licenses_dir.display());
+
+    let mut entries: Vec<(String, String)> = std::fs::read_dir(licenses_dir)
+        .expect("data/licenses should exist")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("txt"))
+        .map(|entry| {
+            let path = entry.path();
+            let spdx_id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .expect("license file name should be valid UTF-8")
+                .to_owned();
+            let text = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+            (spdx_id, text)
+        })
+        .collect();
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let encoded = bincode::serialize(&entries).expect("license corpus should serialize");
+    let compressed = zstd::encode_all(&encoded[..], 19).expect("license corpus should compress");
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR should be set by cargo");
+    let dest = Path::new(&out_dir).join("license-corpus.bin.zst");
+    std::fs::write(&dest, compressed)
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", dest.display(), e));
+}
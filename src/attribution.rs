@@ -0,0 +1,246 @@
+//! Third-party attribution document generation.
+//!
+//! Walks the resolved dependency graph and assembles a single document
+//! listing every dependency's license, full license text, and any `NOTICE`
+//! contents it requires redistributors to carry — the kind of
+//! `THIRD-PARTY-NOTICES` file commonly required when shipping a binary that
+//! bundles permissively-licensed dependencies.
+
+use crate::licenses::{self, Copyright, LicenseInfo};
+use crate::{CrateDetails, Crates};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+/// Everything needed to credit and satisfy the notice requirements of a
+/// single crate's license(s).
+#[derive(Debug, Clone)]
+pub struct CrateAttribution {
+    pub name: String,
+    pub version: crate::Version,
+    /// The crate's `Cargo.toml` `license` field, verbatim, so `AND`/`OR`
+    /// structure survives into the rendered document (unlike `spdx_ids`,
+    /// which has no way to represent "applies together" vs. "pick one").
+    pub license_expression: Option<String>,
+    /// Additional license ids found on disk (identified license files,
+    /// REUSE annotations) that aren't part of the `Cargo.toml` expression.
+    pub spdx_ids: Vec<String>,
+    pub license_texts: Vec<String>,
+    pub notices: Vec<String>,
+    pub copyrights: Vec<Copyright>,
+}
+
+impl CrateDetails {
+    /// Gathers the detected license(s), full license text, `NOTICE`
+    /// contents, and copyright lines needed to credit this crate in a
+    /// third-party attribution document.
+    pub fn attribution(&self) -> CrateAttribution {
+        let license_expression = self
+            .license
+            .expression()
+            .ok()
+            .map(|_| self.license.raw().to_owned());
+
+        let mut spdx_ids = Vec::new();
+        let mut license_texts = Vec::new();
+        let mut notices = Vec::new();
+        let mut copyrights = Vec::new();
+
+        for info in self.licenses() {
+            match info {
+                // Captured above as `license_expression`, which preserves
+                // the `AND`/`OR` structure this flat id list can't.
+                LicenseInfo::Metadata(_) => {}
+                LicenseInfo::IdentifiedLicenseFile { path, spdx_id, .. } => {
+                    spdx_ids.push(spdx_id.to_owned());
+                    if let Ok(text) = std::fs::read_to_string(&path) {
+                        copyrights.extend(licenses::extract_copyrights(&text));
+                        license_texts.push(text);
+                    }
+                }
+                LicenseInfo::InferredLicenseFile(path) | LicenseInfo::ExplicitLicenseFile(path) => {
+                    if let Ok(text) = std::fs::read_to_string(&path) {
+                        if licenses::is_notice_file(&path) {
+                            notices.push(text);
+                        } else {
+                            copyrights.extend(licenses::extract_copyrights(&text));
+                            license_texts.push(text);
+                        }
+                    }
+                }
+                LicenseInfo::ReuseAnnotated(annotation) => {
+                    spdx_ids.push(annotation.spdx_expression.clone());
+                    for text in &annotation.copyright_texts {
+                        copyrights.extend(licenses::extract_copyrights(&format!(
+                            "Copyright {}",
+                            text
+                        )));
+                    }
+                }
+            }
+        }
+
+        spdx_ids.sort();
+        spdx_ids.dedup();
+        if let Ok(expr) = self.license.expression() {
+            let in_expression = expr.license_ids();
+            spdx_ids.retain(|id| !in_expression.contains(&id.as_str()));
+        }
+
+        CrateAttribution {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            license_expression,
+            spdx_ids,
+            license_texts,
+            notices,
+            copyrights,
+        }
+    }
+}
+
+/// The license summary line for a crate: its `Cargo.toml` expression,
+/// verbatim, plus any additional ids found on disk that aren't part of it.
+fn license_summary(c: &CrateAttribution) -> String {
+    match &c.license_expression {
+        Some(expr) if c.spdx_ids.is_empty() => expr.clone(),
+        Some(expr) => format!("{} (also: {})", expr, c.spdx_ids.join(", ")),
+        None => c.spdx_ids.join(" OR "),
+    }
+}
+
+/// A complete attribution document covering every crate in a resolved graph.
+pub struct AttributionReport {
+    pub crates: Vec<CrateAttribution>,
+    /// License texts deduplicated by their own content, since most crates
+    /// in a graph ship byte-identical MIT/Apache-2.0 boilerplate. Keying by
+    /// a 32-bit hash instead would risk silently dropping a genuinely
+    /// different license text on collision, and a `BTreeSet` keeps the
+    /// rendered order stable across runs (a `HashMap`'s iteration order
+    /// isn't).
+    unique_texts: BTreeSet<String>,
+}
+
+/// Walks `crates` and assembles the attribution report for the whole graph.
+pub fn collect(crates: &Crates) -> AttributionReport {
+    let mut unique_texts = BTreeSet::new();
+
+    let attributions = crates
+        .iter()
+        .map(|details| {
+            let attribution = details.attribution();
+            for text in &attribution.license_texts {
+                unique_texts.insert(text.clone());
+            }
+            attribution
+        })
+        .collect();
+
+    AttributionReport {
+        crates: attributions,
+        unique_texts,
+    }
+}
+
+impl AttributionReport {
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        for c in &self.crates {
+            let _ = writeln!(out, "{} {}", c.name, c.version);
+            let _ = writeln!(out, "License: {}", license_summary(c));
+            for notice in &c.notices {
+                let _ = writeln!(out, "NOTICE:\n{}", notice);
+            }
+            let _ = writeln!(out);
+        }
+
+        for text in &self.unique_texts {
+            let _ = writeln!(out, "{}\n{}\n", "-".repeat(40), text);
+        }
+
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        for c in &self.crates {
+            let _ = writeln!(out, "## {} {}", c.name, c.version);
+            let _ = writeln!(out, "\nLicense: `{}`\n", license_summary(c));
+            for notice in &c.notices {
+                let _ = writeln!(out, "> {}\n", notice.replace('\n', "\n> "));
+            }
+        }
+
+        for text in &self.unique_texts {
+            let _ = writeln!(out, "```\n{}\n```\n", text);
+        }
+
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<!doctype html>\n<html>\n<body>\n");
+
+        for c in &self.crates {
+            let _ = writeln!(out, "<h2>{} {}</h2>", escape_html(&c.name), c.version);
+            let _ = writeln!(out, "<p>License: {}</p>", escape_html(&license_summary(c)));
+            for notice in &c.notices {
+                let _ = writeln!(out, "<pre>{}</pre>", escape_html(notice));
+            }
+        }
+
+        for text in &self.unique_texts {
+            let _ = writeln!(out, "<pre>{}</pre>", escape_html(text));
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribution(license_expression: Option<&str>, spdx_ids: &[&str]) -> CrateAttribution {
+        CrateAttribution {
+            name: "some-crate".to_owned(),
+            version: crate::Version::new(1, 0, 0),
+            license_expression: license_expression.map(str::to_owned),
+            spdx_ids: spdx_ids.iter().map(|s| (*s).to_owned()).collect(),
+            license_texts: Vec::new(),
+            notices: Vec::new(),
+            copyrights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn license_summary_uses_the_raw_expression_when_theres_nothing_else() {
+        let c = attribution(Some("MIT OR Apache-2.0"), &[]);
+        assert_eq!(license_summary(&c), "MIT OR Apache-2.0");
+    }
+
+    #[test]
+    fn license_summary_appends_additional_ids_found_on_disk() {
+        let c = attribution(Some("MIT"), &["Apache-2.0"]);
+        assert_eq!(license_summary(&c), "MIT (also: Apache-2.0)");
+    }
+
+    #[test]
+    fn license_summary_falls_back_to_discovered_ids_with_no_metadata_expression() {
+        let c = attribution(None, &["MIT", "Apache-2.0"]);
+        assert_eq!(license_summary(&c), "MIT OR Apache-2.0");
+    }
+
+    #[test]
+    fn escape_html_escapes_the_reserved_characters() {
+        assert_eq!(escape_html("<b>A & B</b>"), "&lt;b&gt;A &amp; B&lt;/b&gt;");
+    }
+}
@@ -10,6 +10,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+pub mod attribution;
 pub mod ban;
 pub mod licenses;
 
@@ -110,6 +111,23 @@ impl CrateDetails {
     }
 
     pub fn licenses(&self) -> impl Iterator<Item = LicenseInfo<'_>> {
+        let candidates = self
+            .root
+            .as_ref()
+            .map(|r| licenses::discover_license_files(r, &licenses::walk_files(r)))
+            .unwrap_or_default();
+
+        self.licenses_with_candidates(candidates)
+    }
+
+    /// Like [`Self::licenses`], but reuses an already-discovered candidate
+    /// list instead of walking this crate's root again — for callers (like
+    /// the license coverage tree) that need that same file list for other
+    /// purposes too.
+    pub(crate) fn licenses_with_candidates(
+        &self,
+        candidates: Vec<licenses::LicenseFileCandidate>,
+    ) -> impl Iterator<Item = LicenseInfo<'_>> {
         let root = self.root.as_ref();
         let explicit = self
             .license_file
@@ -119,48 +137,74 @@ impl CrateDetails {
         // metadata licenses + inferred licenses + explicit license
 
         self.license.iter().map(LicenseInfo::Metadata).chain(
-            find_license_files(root)
-                .filter_map(move |found_path| {
+            candidates
+                .into_iter()
+                // A header-only match (an SPDX header atop a source file)
+                // isn't authoritative enough to count as a license file.
+                .filter(|candidate| candidate.kind != licenses::LicenseFileKind::Header)
+                .filter_map(move |candidate| {
                     // If the license is specified in Cargo.toml, just
                     // skip it to differentiate between what *might* be
                     // a license vs what the crate maintainer explicitly
                     // specified *is* a license
                     if let Some(ref specified) = explicit {
-                        if *specified == found_path {
+                        if *specified == candidate.path {
                             return None;
                         }
                     }
 
-                    Some(LicenseInfo::InferredLicenseFile(found_path))
+                    Some(match candidate.spdx_id {
+                        Some(spdx_id) => LicenseInfo::IdentifiedLicenseFile {
+                            path: candidate.path,
+                            spdx_id,
+                            confidence: candidate.confidence,
+                        },
+                        None => LicenseInfo::InferredLicenseFile(candidate.path),
+                    })
                 })
                 .chain(self.license_file.iter().filter_map(move |elf| {
                     root.map(|r| LicenseInfo::ExplicitLicenseFile(r.join(elf)))
-                })),
+                }))
+                .chain(
+                    root.map(|r| licenses::find_reuse_annotations(r))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(LicenseInfo::ReuseAnnotated),
+                ),
         )
     }
-}
 
-fn find_license_files(dir: Option<&PathBuf>) -> Box<dyn Iterator<Item = PathBuf>> {
-    if let Some(dir) = dir {
-        if let Ok(entries) = std::fs::read_dir(dir) {
-            return Box::new(entries.filter_map(|e| {
-                e.ok().and_then(|e| {
-                    let p = e.path();
-                    if p.is_file()
-                        && p.file_name()
-                            .and_then(|name| name.to_str().map(|name| name.starts_with("LICENSE")))
-                            == Some(true)
-                    {
-                        Some(p)
-                    } else {
-                        None
-                    }
-                })
-            }));
+    /// Resolves the effective license expression for a single file under
+    /// this crate's root, honoring any REUSE `precedence = "override"`
+    /// annotation that covers it ahead of the crate's manifest license.
+    pub fn license_for_path(&self, path: &Path) -> Option<String> {
+        let annotations = self
+            .root
+            .as_ref()
+            .map(|r| licenses::find_reuse_annotations(r))
+            .unwrap_or_default();
+
+        if let Some(overriding) = annotations
+            .iter()
+            .find(|a| a.precedence == licenses::Precedence::Override && a.matches(path))
+        {
+            return Some(overriding.spdx_expression.clone());
         }
-    }
 
-    Box::new(std::iter::empty())
+        let manifest = self
+            .license
+            .expression()
+            .ok()
+            .map(|_| self.license.raw().to_owned());
+
+        // A non-override annotation adds to the manifest license rather
+        // than replacing it.
+        match (annotations.iter().find(|a| a.matches(path)), manifest) {
+            (Some(a), Some(manifest)) => Some(format!("({}) AND ({})", manifest, a.spdx_expression)),
+            (Some(a), None) => Some(a.spdx_expression.clone()),
+            (None, manifest) => manifest,
+        }
+    }
 }
 
 pub struct Crates {
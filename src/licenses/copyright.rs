@@ -0,0 +1,105 @@
+//! Extraction of `Copyright (c) YEAR HOLDER` style lines from license and
+//! header text, for building attribution documents.
+
+/// A single copyright notice line, e.g. `Copyright (c) 2020 Jane Doe`
+/// parsed into its year range and holder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Copyright {
+    pub years: String,
+    pub holder: String,
+}
+
+/// Scans `text` line by line for copyright notices.
+pub fn extract_copyrights(text: &str) -> Vec<Copyright> {
+    text.lines().filter_map(parse_copyright_line).collect()
+}
+
+fn parse_copyright_line(line: &str) -> Option<Copyright> {
+    let idx = find_ignore_case(line, "copyright")?;
+    let mut rest = line[idx + "copyright".len()..].trim_start();
+
+    for marker in &["(c)", "(C)", "©", ":"] {
+        if let Some(stripped) = rest.strip_prefix(marker) {
+            rest = stripped.trim_start();
+        }
+    }
+
+    let years_end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == ',' || c == ' '))
+        .unwrap_or(rest.len());
+    let years = rest[..years_end].trim();
+    let holder = rest[years_end..].trim();
+
+    if years.is_empty() || holder.is_empty() {
+        return None;
+    }
+
+    Some(Copyright {
+        years: years.to_owned(),
+        holder: holder.to_owned(),
+    })
+}
+
+/// Finds the byte offset of an ASCII case-insensitive match of `needle`
+/// within `haystack`, scanning `haystack`'s own char boundaries.
+///
+/// Matching inside a `haystack.to_lowercase()` copy and reusing the offset
+/// is unsound: some Unicode characters expand under `to_lowercase` (e.g.
+/// Turkish `İ` grows from 2 bytes to 3), so an offset found in the
+/// lowercased copy can land inside a multi-byte character when reused to
+/// slice the original `haystack`, panicking. Scanning `haystack` itself
+/// avoids that entirely.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    debug_assert!(needle.is_ascii());
+    haystack.char_indices().map(|(i, _)| i).find(|&i| {
+        haystack.as_bytes()[i..]
+            .get(..needle.len())
+            .is_some_and(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_typical_copyright_line() {
+        let copyrights = extract_copyrights("Copyright (c) 2020 Jane Doe");
+        assert_eq!(
+            copyrights,
+            vec![Copyright {
+                years: "2020".to_owned(),
+                holder: "Jane Doe".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_a_year_range_and_is_case_insensitive() {
+        let copyrights = extract_copyrights("COPYRIGHT 2018-2021, Some Corp");
+        assert_eq!(
+            copyrights,
+            vec![Copyright {
+                years: "2018-2021,".to_owned(),
+                holder: "Some Corp".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_lines_without_a_copyright_notice() {
+        assert_eq!(extract_copyrights("Permission is hereby granted"), vec![]);
+    }
+
+    #[test]
+    fn does_not_panic_on_unicode_that_expands_under_lowercasing() {
+        // Regression test: `İ` (U+0130) lowercases to `i̇` (2 chars, 3
+        // bytes) from a 2-byte original, which used to shift the match
+        // offset computed in a lowercased copy past a char boundary in
+        // the original line.
+        let line = "İcopyrighté rest of line";
+        // Doesn't matter whether it finds a notice here, just that it
+        // doesn't panic.
+        let _ = extract_copyrights(line);
+    }
+}
@@ -0,0 +1,363 @@
+//! Parsing and evaluation of SPDX license expressions, e.g. `MIT OR
+//! Apache-2.0`, `(MIT AND BSD-3-Clause)`, or `Apache-2.0 WITH LLVM-exception`.
+//!
+//! This is a small recursive-descent parser over the subset of the SPDX
+//! license expression grammar that shows up in `Cargo.toml` `license`
+//! fields: license ids, the `WITH` exception operand, and `AND`/`OR` with
+//! parenthesized precedence. `OR` binds more loosely than `AND`, matching
+//! the SPDX spec.
+
+use super::spdx_ids;
+use std::collections::HashSet;
+use std::fmt;
+
+pub type AllowSet = HashSet<String>;
+pub type DenySet = HashSet<String>;
+
+/// The result of evaluating a license expression against an allow/deny policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Allowed,
+    Denied,
+}
+
+/// A parsed SPDX license expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Id(String),
+    WithException(String, String),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Expression {
+    pub fn parse(raw: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(raw)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+
+        let expr = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(ParseError {
+                message: format!("unexpected trailing tokens in license expression `{}`", raw),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Evaluates this expression against an allow/deny policy: an `OR` node
+    /// passes if *any* branch is allowed, an `AND` node passes only if *all*
+    /// branches are allowed. A bare license id is allowed iff it's in
+    /// `allowed` and not in `denied` (`denied` takes precedence).
+    pub fn satisfies(&self, allowed: &AllowSet, denied: &DenySet) -> Outcome {
+        match self {
+            Expression::Id(id) | Expression::WithException(id, _) => {
+                license_outcome(id, allowed, denied)
+            }
+            Expression::And(lhs, rhs) => {
+                if lhs.satisfies(allowed, denied) == Outcome::Allowed
+                    && rhs.satisfies(allowed, denied) == Outcome::Allowed
+                {
+                    Outcome::Allowed
+                } else {
+                    Outcome::Denied
+                }
+            }
+            Expression::Or(lhs, rhs) => {
+                if lhs.satisfies(allowed, denied) == Outcome::Allowed
+                    || rhs.satisfies(allowed, denied) == Outcome::Allowed
+                {
+                    Outcome::Allowed
+                } else {
+                    Outcome::Denied
+                }
+            }
+        }
+    }
+
+    /// The license ids referenced anywhere in this expression, in the order
+    /// they appear (exception ids from `WITH` are not included).
+    ///
+    /// This flattens away whether ids were joined by `AND` or `OR`, so it's
+    /// only suitable for membership checks (allow/deny policy, "does this
+    /// id appear at all"); rendering a human-facing summary of the
+    /// expression needs the original string or the `Expression` tree
+    /// itself, not this list.
+    pub fn license_ids(&self) -> Vec<&str> {
+        match self {
+            Expression::Id(id) | Expression::WithException(id, _) => vec![id.as_str()],
+            Expression::And(lhs, rhs) | Expression::Or(lhs, rhs) => {
+                let mut ids = lhs.license_ids();
+                ids.extend(rhs.license_ids());
+                ids
+            }
+        }
+    }
+}
+
+fn license_outcome(id: &str, allowed: &AllowSet, denied: &DenySet) -> Outcome {
+    if denied.contains(id) {
+        Outcome::Denied
+    } else if allowed.contains(id) {
+        Outcome::Allowed
+    } else {
+        Outcome::Denied
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    And,
+    Or,
+    With,
+    LParen,
+    RParen,
+}
+
+fn tokenize(raw: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = raw.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                chars.next();
+            }
+            _ => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+
+                tokens.push(match ident.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "WITH" => Token::With,
+                    _ => Token::Ident(ident),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(ParseError {
+                message: format!("expected `{:?}`, found `{:?}`", token, self.peek()),
+            })
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            other => Err(ParseError {
+                message: format!("expected a license id, found `{:?}`", other),
+            }),
+        }
+    }
+
+    // `or-expr := and-expr ("OR" and-expr)*`
+    fn parse_or(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            let rhs = self.parse_and()?;
+            lhs = Expression::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // `and-expr := with-expr ("AND" with-expr)*`
+    fn parse_and(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.parse_with()?;
+        while self.eat(&Token::And) {
+            let rhs = self.parse_with()?;
+            lhs = Expression::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // `with-expr := atom ("WITH" ident)?`
+    fn parse_with(&mut self) -> Result<Expression, ParseError> {
+        let lhs = self.parse_atom()?;
+        if self.eat(&Token::With) {
+            let exception = self.expect_ident()?;
+            return match lhs {
+                Expression::Id(id) => Ok(Expression::WithException(id, exception)),
+                _ => Err(ParseError {
+                    message: "`WITH` may only follow a single license id".to_owned(),
+                }),
+            };
+        }
+        Ok(lhs)
+    }
+
+    // `atom := "(" or-expr ")" | license-id`
+    fn parse_atom(&mut self) -> Result<Expression, ParseError> {
+        if self.eat(&Token::LParen) {
+            let expr = self.parse_or()?;
+            self.expect(&Token::RParen)?;
+            return Ok(expr);
+        }
+
+        let id = self.expect_ident()?;
+        if !spdx_ids::is_known(&id) {
+            return Err(ParseError {
+                message: format!("unknown SPDX license id `{}`", id),
+            });
+        }
+
+        Ok(Expression::Id(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_or() {
+        let expr = Expression::parse("MIT OR Apache-2.0").unwrap();
+        assert_eq!(
+            expr,
+            Expression::Or(
+                Box::new(Expression::Id("MIT".to_owned())),
+                Box::new(Expression::Id("Apache-2.0".to_owned())),
+            )
+        );
+    }
+
+    #[test]
+    fn parses_and_distinctly_from_or() {
+        let and_expr = Expression::parse("Apache-2.0 AND BSD-3-Clause").unwrap();
+        let or_expr = Expression::parse("Apache-2.0 OR BSD-3-Clause").unwrap();
+        assert_ne!(and_expr, or_expr);
+        assert!(matches!(and_expr, Expression::And(..)));
+        assert!(matches!(or_expr, Expression::Or(..)));
+    }
+
+    #[test]
+    fn or_binds_looser_than_and() {
+        // `MIT OR Apache-2.0 AND BSD-3-Clause` must parse as
+        // `MIT OR (Apache-2.0 AND BSD-3-Clause)`, not `(MIT OR Apache-2.0) AND BSD-3-Clause`.
+        let expr = Expression::parse("MIT OR Apache-2.0 AND BSD-3-Clause").unwrap();
+        match expr {
+            Expression::Or(lhs, rhs) => {
+                assert_eq!(*lhs, Expression::Id("MIT".to_owned()));
+                assert!(matches!(*rhs, Expression::And(..)));
+            }
+            other => panic!("expected a top-level OR, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_with_exception() {
+        let expr = Expression::parse("Apache-2.0 WITH LLVM-exception").unwrap();
+        assert_eq!(
+            expr,
+            Expression::WithException("Apache-2.0".to_owned(), "LLVM-exception".to_owned())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_license_id() {
+        assert!(Expression::parse("NotARealLicense-1.0").is_err());
+    }
+
+    #[test]
+    fn and_requires_all_branches_allowed() {
+        let expr = Expression::parse("Apache-2.0 AND BSD-3-Clause").unwrap();
+        let allowed: AllowSet = vec!["Apache-2.0".to_owned()].into_iter().collect();
+        let denied = DenySet::new();
+
+        // Only one of the two required licenses is allowed, so the whole
+        // AND expression must be denied, not allowed.
+        assert_eq!(expr.satisfies(&allowed, &denied), Outcome::Denied);
+    }
+
+    #[test]
+    fn or_is_allowed_if_any_branch_is_allowed() {
+        let expr = Expression::parse("MIT OR Apache-2.0").unwrap();
+        let allowed: AllowSet = vec!["Apache-2.0".to_owned()].into_iter().collect();
+        let denied = DenySet::new();
+
+        assert_eq!(expr.satisfies(&allowed, &denied), Outcome::Allowed);
+    }
+
+    #[test]
+    fn denied_takes_precedence_over_allowed() {
+        let expr = Expression::parse("MIT").unwrap();
+        let allowed: AllowSet = vec!["MIT".to_owned()].into_iter().collect();
+        let denied: DenySet = vec!["MIT".to_owned()].into_iter().collect();
+
+        assert_eq!(expr.satisfies(&allowed, &denied), Outcome::Denied);
+    }
+
+    #[test]
+    fn license_ids_flattens_and_and_or_without_distinction() {
+        // Documents the limitation called out on `license_ids`: callers
+        // that need to know whether ids are joined by AND or OR (e.g. a
+        // rendered summary) must not use this method.
+        let and_expr = Expression::parse("Apache-2.0 AND BSD-3-Clause").unwrap();
+        let or_expr = Expression::parse("Apache-2.0 OR BSD-3-Clause").unwrap();
+        assert_eq!(and_expr.license_ids(), or_expr.license_ids());
+    }
+}
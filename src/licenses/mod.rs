@@ -0,0 +1,292 @@
+//! License detection for crates in the dependency graph.
+//!
+//! A crate's license is assembled from several sources: the `license` field
+//! in its `Cargo.toml`, any `LICENSE`-like files discovered on disk, and
+//! REUSE file annotations. This module is the home for all
+//! of that, plus the logic that turns unlabeled license text into an actual
+//! SPDX identifier.
+
+mod copyright;
+mod expr;
+mod path_tree;
+mod reuse;
+mod spdx_ids;
+mod store;
+
+use std::path::{Path, PathBuf};
+
+pub use copyright::{extract_copyrights, Copyright};
+pub use expr::{AllowSet, DenySet, Expression, Outcome, ParseError};
+pub use path_tree::{check_coverage, CoverageCheck, CoverageReport, LicenseSet, PathTree};
+pub(crate) use path_tree::walk_files;
+pub use reuse::{Precedence, ReuseAnnotation};
+
+/// The `license` field of a `Cargo.toml`, e.g. `"MIT/Apache-2.0"` or
+/// `"MIT OR Apache-2.0"`, parsed into an [`Expression`].
+///
+/// The legacy slash-separated form predates SPDX expressions in Cargo and is
+/// treated as equivalent to `OR`.
+#[derive(Debug, Clone)]
+pub struct LicenseField {
+    raw: String,
+    parsed: Result<Expression, ParseError>,
+}
+
+impl Default for LicenseField {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl LicenseField {
+    pub fn new(raw: String) -> Self {
+        let normalized = raw.replace('/', " OR ");
+        let parsed = if normalized.trim().is_empty() {
+            Err(ParseError {
+                message: "empty `license` field".to_owned(),
+            })
+        } else {
+            Expression::parse(&normalized)
+        };
+
+        Self { raw, parsed }
+    }
+
+    /// The original, unparsed `license` field.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// The parsed SPDX expression, or the error encountered while parsing it.
+    pub fn expression(&self) -> Result<&Expression, &ParseError> {
+        self.parsed.as_ref()
+    }
+
+    /// Whether this expression satisfies the given allow/deny policy. A
+    /// field that failed to parse is treated as denied, since an
+    /// unrecognized expression can't be proven to be in the allow set.
+    pub fn satisfies(&self, allowed: &AllowSet, denied: &DenySet) -> Outcome {
+        match &self.parsed {
+            Ok(expr) => expr.satisfies(allowed, denied),
+            Err(_) => Outcome::Denied,
+        }
+    }
+
+    /// The individual license identifiers making up this field.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.parsed
+            .as_ref()
+            .map(Expression::license_ids)
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+/// A single piece of evidence about what license(s) apply to a crate.
+pub enum LicenseInfo<'a> {
+    /// A license id taken straight from the `Cargo.toml` `license` field.
+    Metadata(&'a str),
+    /// A discovered `LICENSE`-like file that could not be confidently
+    /// identified as a specific SPDX license.
+    InferredLicenseFile(PathBuf),
+    /// The file named by the `license-file` field in `Cargo.toml`.
+    ExplicitLicenseFile(PathBuf),
+    /// A discovered license file whose text was matched against the SPDX
+    /// corpus with high enough confidence to name the license it contains.
+    IdentifiedLicenseFile {
+        path: PathBuf,
+        spdx_id: String,
+        confidence: f32,
+    },
+    /// A path-scoped license expression from a `REUSE.toml` or `.reuse/dep5`
+    /// annotation.
+    ReuseAnnotated(ReuseAnnotation),
+}
+
+/// Filename prefixes that are worth scanning as potential license evidence.
+/// `LICENSE` covers the canonical case (and its `-MIT`/`-APACHE` suffixed
+/// variants); `COPYING` is the GNU convention; `UNLICENSE` and `NOTICE` are
+/// common enough on crates.io to be worth the same treatment.
+const LICENSE_FILE_PREFIXES: &[&str] = &["LICENSE", "COPYING", "UNLICENSE", "NOTICE"];
+
+/// Below this fraction of a file's tokens accounted for by the matched
+/// license text, a match is a fragment (a header) rather than the file's
+/// whole content.
+const HEADER_COVERAGE_THRESHOLD: f32 = 0.6;
+
+/// What role a discovered license-like file plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseFileKind {
+    /// The file's content *is* (essentially) a canonical license body.
+    Text,
+    /// A license-like file whose matched license text accounts for less
+    /// than [`HEADER_COVERAGE_THRESHOLD`] of its tokens, i.e. a license
+    /// fragment with substantial unmatched content around it. Only files
+    /// with a [`LICENSE_FILE_PREFIXES`] name are classified; other source
+    /// files are not examined for SPDX headers. Not authoritative enough to
+    /// name the crate's license.
+    Header,
+    /// Full license text that applies to a sub-path root, e.g. a vendored
+    /// dependency's own `LICENSE` file nested under the crate root.
+    AddendumText,
+}
+
+/// A discovered license-like file, classified and scored against the SPDX
+/// corpus.
+#[derive(Debug, Clone)]
+pub struct LicenseFileCandidate {
+    pub path: PathBuf,
+    pub kind: LicenseFileKind,
+    pub spdx_id: Option<String>,
+    pub confidence: f32,
+}
+
+/// Classifies and scores every license-like file in `files` (a prior
+/// [`path_tree::walk_files`] result), returning them sorted by descending
+/// confidence so a high-confidence full-text match is preferred over a
+/// low-confidence partial one.
+///
+/// Takes an already-walked file list rather than walking `root` itself, so
+/// callers that also need the raw file list (or call this more than once)
+/// don't each pay for their own recursive directory walk.
+pub(crate) fn discover_license_files(root: &Path, files: &[PathBuf]) -> Vec<LicenseFileCandidate> {
+    let mut candidates: Vec<_> = files
+        .iter()
+        .filter(|path| is_license_like_name(path))
+        .map(|path| classify(root, path.clone()))
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    candidates
+}
+
+fn classify(root: &Path, path: PathBuf) -> LicenseFileCandidate {
+    let text = std::fs::read_to_string(&path).unwrap_or_default();
+    let is_root_level = path.parent().map(|p| p == root).unwrap_or(false);
+
+    let (spdx_id, confidence, kind) = match store::identify_with_coverage(&text) {
+        Some((id, confidence, coverage)) if coverage >= HEADER_COVERAGE_THRESHOLD => {
+            let kind = if is_root_level {
+                LicenseFileKind::Text
+            } else {
+                LicenseFileKind::AddendumText
+            };
+            (Some(id), confidence, kind)
+        }
+        Some((id, confidence, _)) => (Some(id), confidence, LicenseFileKind::Header),
+        None => {
+            let kind = if is_root_level {
+                LicenseFileKind::Text
+            } else {
+                LicenseFileKind::AddendumText
+            };
+            (None, 0.0, kind)
+        }
+    };
+
+    LicenseFileCandidate {
+        path,
+        kind,
+        spdx_id,
+        confidence,
+    }
+}
+
+fn is_license_like_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| {
+            LICENSE_FILE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .unwrap_or(false)
+}
+
+/// Whether `path` is a `NOTICE`-style file, as opposed to a license body.
+pub(crate) fn is_notice_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with("NOTICE"))
+        .unwrap_or(false)
+}
+
+/// Loads the REUSE annotations (if any) for a crate rooted at `root`.
+pub(crate) fn find_reuse_annotations(root: &Path) -> Vec<ReuseAnnotation> {
+    reuse::load(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIT_TEXT: &str = include_str!("../../data/licenses/MIT.txt");
+
+    fn with_temp_dir(name: &str, f: impl FnOnce(&Path)) {
+        let root = std::env::temp_dir().join(format!("cargo-deny-test-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(root.join("vendor/openssl")).expect("create dirs");
+        f(&root);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn classify_recognizes_a_root_level_full_license_text() {
+        with_temp_dir("classify-root-text", |root| {
+            let path = root.join("LICENSE-MIT");
+            std::fs::write(&path, MIT_TEXT).unwrap();
+
+            let candidate = classify(root, path);
+            assert_eq!(candidate.kind, LicenseFileKind::Text);
+            assert_eq!(candidate.spdx_id.as_deref(), Some("MIT"));
+        });
+    }
+
+    #[test]
+    fn classify_marks_a_nested_full_license_text_as_an_addendum() {
+        with_temp_dir("classify-nested-text", |root| {
+            let path = root.join("vendor/openssl/LICENSE");
+            std::fs::write(&path, MIT_TEXT).unwrap();
+
+            let candidate = classify(root, path);
+            assert_eq!(candidate.kind, LicenseFileKind::AddendumText);
+            assert_eq!(candidate.spdx_id.as_deref(), Some("MIT"));
+        });
+    }
+
+    #[test]
+    fn classify_marks_unidentified_root_text_as_text_not_addendum() {
+        with_temp_dir("classify-root-unidentified", |root| {
+            let path = root.join("LICENSE");
+            std::fs::write(&path, "Some bespoke license nobody wrote a matcher for.").unwrap();
+
+            let candidate = classify(root, path);
+            assert_eq!(candidate.kind, LicenseFileKind::Text);
+            assert_eq!(candidate.spdx_id, None);
+        });
+    }
+
+    #[test]
+    fn classify_marks_unidentified_nested_text_as_an_addendum_not_the_crates_own_license() {
+        with_temp_dir("classify-nested-unidentified", |root| {
+            let path = root.join("vendor/openssl/LICENSE");
+            std::fs::write(&path, "Some bespoke license nobody wrote a matcher for.").unwrap();
+
+            let candidate = classify(root, path);
+            assert_eq!(candidate.kind, LicenseFileKind::AddendumText);
+            assert_eq!(candidate.spdx_id, None);
+        });
+    }
+
+    #[test]
+    fn is_license_like_name_matches_known_prefixes_only() {
+        assert!(is_license_like_name(Path::new("LICENSE-MIT")));
+        assert!(is_license_like_name(Path::new("COPYING")));
+        assert!(is_license_like_name(Path::new("NOTICE")));
+        assert!(!is_license_like_name(Path::new("README.md")));
+    }
+}
@@ -0,0 +1,483 @@
+//! A per-file license coverage tree, so crate authors and `cargo-deny` users
+//! can find source files that no license evidence actually covers —
+//! REUSE-style completeness auditing.
+//!
+//! Every file under a crate's root is inserted as a leaf reached by walking
+//! its directory components. A leaf can carry its own license attribution
+//! (from a REUSE per-file annotation); a directory can carry an *addendum*
+//! license that's inherited by everything beneath it (the common case: a
+//! `LICENSE` file covering every file in its directory tree). Once built,
+//! the tree is collapsed bottom-up: any directory whose files all resolve
+//! to the same effective license set is represented as a single node,
+//! keeping the report small for the common case of one license per crate.
+
+use super::ReuseAnnotation;
+use crate::{CrateDetails, LintLevel};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// The set of SPDX license expressions attributed to a path.
+pub type LicenseSet = BTreeSet<String>;
+
+enum Node {
+    Leaf(LicenseSet),
+    Dir {
+        addendum: LicenseSet,
+        children: BTreeMap<String, Node>,
+    },
+}
+
+impl Node {
+    fn new_dir() -> Self {
+        Node::Dir {
+            addendum: LicenseSet::new(),
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+/// A license coverage tree for a single crate.
+pub struct PathTree {
+    root: Node,
+}
+
+impl Default for PathTree {
+    fn default() -> Self {
+        Self { root: Node::new_dir() }
+    }
+}
+
+impl PathTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attributes `licenses` directly to the file at `path`, creating it
+    /// (with an empty set) first if it isn't already present.
+    pub fn insert_file(&mut self, path: &Path, licenses: LicenseSet) {
+        let components = path_components(path);
+        Self::insert_at(&mut self.root, &components, licenses, true);
+    }
+
+    /// Records that `path` exists with no license attribution of its own
+    /// yet, so it shows up in the coverage report even if nothing ever
+    /// attributes a license to it.
+    pub fn touch_file(&mut self, path: &Path) {
+        self.insert_file(path, LicenseSet::new());
+    }
+
+    /// Attributes an addendum license to every file under the directory
+    /// `path` (inclusive), such as a `LICENSE` file found there.
+    pub fn insert_addendum(&mut self, path: &Path, licenses: LicenseSet) {
+        let components = path_components(path);
+        Self::insert_at(&mut self.root, &components, licenses, false);
+    }
+
+    fn insert_at(node: &mut Node, components: &[String], licenses: LicenseSet, leaf: bool) {
+        match components.split_first() {
+            None => match node {
+                Node::Leaf(set) => set.extend(licenses),
+                Node::Dir { addendum, .. } => addendum.extend(licenses),
+            },
+            Some((head, rest)) => {
+                if let Node::Dir { children, .. } = node {
+                    let child = children
+                        .entry(head.clone())
+                        .or_insert_with(|| {
+                            if rest.is_empty() && leaf {
+                                Node::Leaf(LicenseSet::new())
+                            } else {
+                                Node::new_dir()
+                            }
+                        });
+                    Self::insert_at(child, rest, licenses, leaf);
+                }
+            }
+        }
+    }
+
+    /// Collapses the tree bottom-up and returns the compact coverage report.
+    pub fn report(&self) -> CoverageReport {
+        let resolved = resolve(&self.root, &LicenseSet::new());
+
+        let mut covered = Vec::new();
+        let mut unlicensed = Vec::new();
+        flatten(&resolved, String::new(), &mut covered, &mut unlicensed);
+
+        covered.sort();
+        unlicensed.sort();
+
+        CoverageReport { covered, unlicensed }
+    }
+}
+
+fn path_components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str().map(str::to_owned))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Resolved {
+    /// A path resolving uniformly to this license set. An empty set means
+    /// the path has no license coverage at all.
+    Uniform(LicenseSet),
+    /// A directory whose children resolve to different license sets.
+    Divergent(BTreeMap<String, Resolved>),
+}
+
+fn resolve(node: &Node, inherited: &LicenseSet) -> Resolved {
+    match node {
+        Node::Leaf(own) => {
+            let mut full = inherited.clone();
+            full.extend(own.iter().cloned());
+            Resolved::Uniform(full)
+        }
+        Node::Dir { addendum, children } => {
+            let mut inherited = inherited.clone();
+            inherited.extend(addendum.iter().cloned());
+
+            if children.is_empty() {
+                return Resolved::Uniform(inherited);
+            }
+
+            let resolved: BTreeMap<String, Resolved> = children
+                .iter()
+                .map(|(name, child)| (name.clone(), resolve(child, &inherited)))
+                .collect();
+
+            let mut values = resolved.values();
+            let first = values.next();
+            let collapses = match first {
+                Some(Resolved::Uniform(set)) => {
+                    values.clone().all(|v| v == &Resolved::Uniform(set.clone()))
+                }
+                _ => false,
+            };
+
+            if collapses {
+                first.cloned().unwrap()
+            } else {
+                Resolved::Divergent(resolved)
+            }
+        }
+    }
+}
+
+fn flatten(
+    resolved: &Resolved,
+    prefix: String,
+    covered: &mut Vec<(String, LicenseSet)>,
+    unlicensed: &mut Vec<String>,
+) {
+    match resolved {
+        Resolved::Uniform(set) => {
+            let label = if prefix.is_empty() { ".".to_owned() } else { prefix };
+            if set.is_empty() {
+                unlicensed.push(label);
+            } else {
+                covered.push((label, set.clone()));
+            }
+        }
+        Resolved::Divergent(children) => {
+            for (name, child) in children {
+                let path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", prefix, name)
+                };
+                flatten(child, path, covered, unlicensed);
+            }
+        }
+    }
+}
+
+/// The result of collapsing a [`PathTree`]: the compact `path -> licenses`
+/// mapping, plus the explicit list of paths with no coverage at all.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    pub covered: Vec<(String, LicenseSet)>,
+    pub unlicensed: Vec<String>,
+}
+
+/// The outcome of running the "unlicensed files" check at a given
+/// [`LintLevel`].
+pub struct CoverageCheck {
+    pub report: CoverageReport,
+    pub level: LintLevel,
+}
+
+impl CoverageCheck {
+    pub fn is_deny(&self) -> bool {
+        self.level == LintLevel::Deny && !self.report.unlicensed.is_empty()
+    }
+
+    pub fn is_warn(&self) -> bool {
+        self.level == LintLevel::Warn && !self.report.unlicensed.is_empty()
+    }
+}
+
+/// Runs the license-coverage check against `tree` at the given `level`.
+pub fn check_coverage(tree: &PathTree, level: LintLevel) -> CoverageCheck {
+    CoverageCheck {
+        report: tree.report(),
+        level,
+    }
+}
+
+impl CrateDetails {
+    /// Builds the license coverage tree for every source file under this
+    /// crate's root, or `None` if the crate's root isn't known.
+    pub fn license_coverage(&self) -> Option<PathTree> {
+        build_for_crate(self)
+    }
+}
+
+/// A placeholder inserted for a license file the tree knows about — an
+/// explicit `license-file` from `Cargo.toml`, or a discovered `LICENSE`-like
+/// file whose text didn't confidently match a specific SPDX id — but can't
+/// name. Without this, a crate whose license text doesn't hit the corpus's
+/// confidence threshold (a reflowed paragraph, an older license version, a
+/// custom header) would have its entire file tree reported as unlicensed by
+/// `check_coverage`, despite having a license file sitting right there.
+const UNIDENTIFIED_LICENSE: &str = "<unidentified license file>";
+
+/// Builds a license coverage tree for every source file under a crate's
+/// root. `LICENSE`-like files contribute a directory addendum covering
+/// everything beneath the directory they were found in; REUSE per-file
+/// annotations and `Header`-kind license candidates contribute leaf
+/// attributions for the files they match. Source files themselves are not
+/// scanned for SPDX headers.
+fn build_for_crate(details: &CrateDetails) -> Option<PathTree> {
+    let root = details.root.clone()?;
+    let mut tree = PathTree::new();
+    let files = walk_files(&root);
+    // Walked and classified once, then reused both for `details.licenses()`
+    // below and for the header-only pass further down — otherwise each of
+    // those would walk `root` from scratch again.
+    let candidates = super::discover_license_files(&root, &files);
+
+    for info in details.licenses_with_candidates(candidates.clone()) {
+        match info {
+            super::LicenseInfo::IdentifiedLicenseFile { path, spdx_id, .. } => {
+                if let Some(dir) = path.parent().and_then(|p| p.strip_prefix(&root).ok()) {
+                    let mut set = LicenseSet::new();
+                    set.insert(spdx_id.to_owned());
+                    tree.insert_addendum(dir, set);
+                }
+            }
+            super::LicenseInfo::ExplicitLicenseFile(path)
+            | super::LicenseInfo::InferredLicenseFile(path) => {
+                if let Some(dir) = path.parent().and_then(|p| p.strip_prefix(&root).ok()) {
+                    let mut set = LicenseSet::new();
+                    set.insert(UNIDENTIFIED_LICENSE.to_owned());
+                    tree.insert_addendum(dir, set);
+                }
+            }
+            super::LicenseInfo::ReuseAnnotated(annotation) => {
+                attribute_reuse_matches(&mut tree, &root, &files, &annotation);
+            }
+            super::LicenseInfo::Metadata(_) => {}
+        }
+    }
+
+    // A header-only match doesn't make `details.licenses()`, since it isn't
+    // authoritative for the *crate's* license, but it's still exactly the
+    // per-file evidence this tree is meant to track.
+    for candidate in candidates {
+        if candidate.kind != super::LicenseFileKind::Header {
+            continue;
+        }
+        if let (Some(spdx_id), Ok(rel)) = (candidate.spdx_id, candidate.path.strip_prefix(&root)) {
+            let mut set = LicenseSet::new();
+            set.insert(spdx_id.to_owned());
+            tree.insert_file(rel, set);
+        }
+    }
+
+    for file in &files {
+        if let Ok(rel) = file.strip_prefix(&root) {
+            tree.touch_file(rel);
+        }
+    }
+
+    Some(tree)
+}
+
+fn attribute_reuse_matches(
+    tree: &mut PathTree,
+    root: &Path,
+    files: &[PathBuf],
+    annotation: &ReuseAnnotation,
+) {
+    let mut set = LicenseSet::new();
+    set.insert(annotation.spdx_expression.clone());
+
+    for file in files {
+        if let Ok(rel) = file.strip_prefix(root) {
+            if annotation.matches(rel) {
+                tree.insert_file(rel, set.clone());
+            }
+        }
+    }
+}
+
+/// Recursively collects every regular file under `root`, skipping VCS and
+/// build-output directories.
+pub(crate) fn walk_files(root: &Path) -> Vec<PathBuf> {
+    const SKIPPED_DIRS: &[&str] = &[".git", "target"];
+
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                if path.is_dir() {
+                    let skip = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| SKIPPED_DIRS.contains(&n))
+                        .unwrap_or(false);
+                    if !skip {
+                        stack.push(path);
+                    }
+                } else if path.is_file() {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIT_TEXT: &str = "MIT License\n\nCopyright (c) <year> <copyright holders>\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\nof this software and associated documentation files (the \"Software\"), to deal\nin the Software without restriction, including without limitation the rights\nto use, copy, modify, merge, publish, distribute, sublicense, and/or sell\ncopies of the Software, and to permit persons to whom the Software is\nfurnished to do so, subject to the following conditions:\n\nThe above copyright notice and this permission notice shall be included in all\ncopies or substantial portions of the Software.\n\nTHE SOFTWARE IS PROVIDED \"AS IS\", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR\nIMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,\nFITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE\nAUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER\nLIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,\nOUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE\nSOFTWARE.\n";
+
+    fn set(ids: &[&str]) -> LicenseSet {
+        ids.iter().map(|s| (*s).to_owned()).collect()
+    }
+
+    #[test]
+    fn touched_file_with_no_attribution_is_unlicensed() {
+        let mut tree = PathTree::new();
+        tree.touch_file(Path::new("src/lib.rs"));
+
+        let report = tree.report();
+        assert_eq!(report.covered, vec![]);
+        // The single-child chain root -> src -> lib.rs collapses all the
+        // way up, since it resolves uniformly to "no license" at every
+        // level.
+        assert_eq!(report.unlicensed, vec![".".to_owned()]);
+    }
+
+    #[test]
+    fn addendum_covers_every_file_beneath_it() {
+        let mut tree = PathTree::new();
+        tree.insert_addendum(Path::new(""), set(&["MIT"]));
+        tree.touch_file(Path::new("src/lib.rs"));
+        tree.touch_file(Path::new("src/main.rs"));
+
+        let report = tree.report();
+        // Both files resolve to the same set, so the tree collapses to a
+        // single root-level entry instead of reporting each file.
+        assert_eq!(report.covered, vec![(".".to_owned(), set(&["MIT"]))]);
+        assert!(report.unlicensed.is_empty());
+    }
+
+    #[test]
+    fn diverging_licenses_do_not_collapse() {
+        let mut tree = PathTree::new();
+        tree.insert_addendum(Path::new("vendor/openssl"), set(&["Apache-2.0"]));
+        tree.touch_file(Path::new("vendor/openssl/lib.c"));
+        tree.touch_file(Path::new("src/lib.rs"));
+
+        let report = tree.report();
+        // `vendor` and `src` resolve to different license sets, so the
+        // root doesn't collapse; but `vendor/openssl` is itself a uniform
+        // single-child chain and collapses up to its `vendor` label.
+        assert_eq!(report.covered, vec![("vendor".to_owned(), set(&["Apache-2.0"]))]);
+        assert_eq!(report.unlicensed, vec!["src".to_owned()]);
+    }
+
+    #[test]
+    fn a_leaf_attribution_adds_to_an_inherited_addendum() {
+        let mut tree = PathTree::new();
+        tree.insert_addendum(Path::new(""), set(&["MIT"]));
+        tree.insert_file(Path::new("src/vendored.rs"), set(&["Apache-2.0"]));
+        tree.touch_file(Path::new("src/lib.rs"));
+
+        let report = tree.report();
+        assert!(report.covered.contains(&("src/lib.rs".to_owned(), set(&["MIT"]))));
+        assert!(report
+            .covered
+            .contains(&("src/vendored.rs".to_owned(), set(&["MIT", "Apache-2.0"]))));
+    }
+
+    fn with_temp_crate_root(name: &str, f: impl FnOnce(&Path)) {
+        let root = std::env::temp_dir().join(format!("cargo-deny-test-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(root.join("src")).expect("create src dir");
+        f(&root);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn walk_files_skips_vcs_and_build_output_dirs() {
+        with_temp_crate_root("walk", |root| {
+            std::fs::write(root.join("src").join("lib.rs"), "fn main() {}").unwrap();
+            std::fs::create_dir_all(root.join(".git")).unwrap();
+            std::fs::write(root.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+            std::fs::create_dir_all(root.join("target")).unwrap();
+            std::fs::write(root.join("target").join("out"), "binary junk").unwrap();
+
+            let files = walk_files(root);
+            assert!(files.iter().any(|p| p.ends_with("src/lib.rs")));
+            assert!(!files.iter().any(|p| p.to_string_lossy().contains(".git")));
+            assert!(!files.iter().any(|p| p.to_string_lossy().contains("target")));
+        });
+    }
+
+    #[test]
+    fn build_for_crate_attributes_a_root_license_to_every_file() {
+        with_temp_crate_root("build", |root| {
+            std::fs::write(root.join("LICENSE-MIT"), MIT_TEXT).unwrap();
+            std::fs::write(root.join("src").join("lib.rs"), "fn main() {}").unwrap();
+
+            let details = CrateDetails {
+                root: Some(root.to_path_buf()),
+                ..CrateDetails::default()
+            };
+
+            let report = details.license_coverage().expect("root is set").report();
+            assert!(report.unlicensed.is_empty());
+            assert_eq!(report.covered, vec![(".".to_owned(), set(&["MIT"]))]);
+        });
+    }
+
+    #[test]
+    fn build_for_crate_falls_back_to_the_unidentified_placeholder() {
+        with_temp_crate_root("build-unidentified", |root| {
+            // Text that won't hit the corpus's confidence threshold, but is
+            // still clearly meant to be a license file.
+            std::fs::write(root.join("LICENSE"), "Some bespoke license nobody wrote a matcher for.").unwrap();
+            std::fs::write(root.join("src").join("lib.rs"), "fn main() {}").unwrap();
+
+            let details = CrateDetails {
+                root: Some(root.to_path_buf()),
+                ..CrateDetails::default()
+            };
+
+            let report = details.license_coverage().expect("root is set").report();
+            assert!(report.unlicensed.is_empty());
+            assert_eq!(
+                report.covered,
+                vec![(".".to_owned(), set(&[UNIDENTIFIED_LICENSE]))]
+            );
+        });
+    }
+}
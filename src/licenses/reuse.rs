@@ -0,0 +1,321 @@
+//! Support for the [REUSE specification](https://reuse.software/), which
+//! lets a crate express per-file licensing via a `REUSE.toml` manifest (or
+//! the older `.reuse/dep5` DEP-5 format) instead of a single top-level
+//! `LICENSE` file.
+
+use std::path::Path;
+
+/// How a REUSE annotation interacts with other license evidence for the
+/// paths it covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precedence {
+    /// The annotation adds to whatever else was found for these paths.
+    Additional,
+    /// The annotation replaces the crate's manifest license for these paths
+    /// when resolving a single path's license
+    /// (`CrateDetails::license_for_path`). The crate-level license list and
+    /// the coverage tree treat it the same as `Additional`.
+    Override,
+}
+
+impl Default for Precedence {
+    fn default() -> Self {
+        Precedence::Additional
+    }
+}
+
+/// A single REUSE annotation: the paths it covers, the SPDX expression that
+/// applies to them, and any copyright texts.
+#[derive(Debug, Clone)]
+pub struct ReuseAnnotation {
+    pub path_glob: String,
+    pub spdx_expression: String,
+    pub copyright_texts: Vec<String>,
+    pub precedence: Precedence,
+}
+
+impl ReuseAnnotation {
+    /// Whether `path` (relative to the crate root) is covered by this
+    /// annotation's glob.
+    pub fn matches(&self, path: &Path) -> bool {
+        glob_match(&self.path_glob, &path.to_string_lossy())
+    }
+}
+
+/// Loads REUSE annotations for a crate rooted at `root`, preferring
+/// `REUSE.toml` and falling back to `.reuse/dep5` if present.
+pub fn load(root: &Path) -> Vec<ReuseAnnotation> {
+    if let Some(annotations) = load_reuse_toml(&root.join("REUSE.toml")) {
+        return annotations;
+    }
+
+    load_dep5(&root.join(".reuse").join("dep5")).unwrap_or_default()
+}
+
+fn load_reuse_toml(path: &Path) -> Option<Vec<ReuseAnnotation>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let doc: toml::Value = contents.parse().ok()?;
+    let annotations = doc.get("annotations")?.as_array()?;
+
+    Some(
+        annotations
+            .iter()
+            .filter_map(|entry| {
+                let path_glob = entry.get("path")?.as_str()?.to_owned();
+                let spdx_expression = entry.get("SPDX-License-Identifier")?.as_str()?.to_owned();
+                let copyright_texts = match entry.get("SPDX-FileCopyrightText") {
+                    Some(toml::Value::String(s)) => vec![s.clone()],
+                    Some(toml::Value::Array(items)) => items
+                        .iter()
+                        .filter_map(|i| i.as_str().map(str::to_owned))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let precedence = match entry.get("precedence").and_then(|v| v.as_str()) {
+                    Some("override") => Precedence::Override,
+                    _ => Precedence::Additional,
+                };
+
+                Some(ReuseAnnotation {
+                    path_glob,
+                    spdx_expression,
+                    copyright_texts,
+                    precedence,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Parses the older `.reuse/dep5` format: a DEP-5 copyright file where each
+/// stanza maps a `Files:` glob to `Copyright:` and `License:` fields.
+fn load_dep5(path: &Path) -> Option<Vec<ReuseAnnotation>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+
+    let mut annotations = Vec::new();
+    let mut files = None;
+    let mut copyrights = Vec::new();
+    let mut license = None;
+
+    for line in contents.lines().chain(std::iter::once("")) {
+        if let Some(value) = line.strip_prefix("Files:") {
+            flush_dep5_stanza(&mut annotations, &mut files, &mut copyrights, &mut license);
+            files = Some(value.trim().to_owned());
+        } else if let Some(value) = line.strip_prefix("Copyright:") {
+            copyrights.push(value.trim().to_owned());
+        } else if let Some(value) = line.strip_prefix("License:") {
+            license = Some(value.trim().to_owned());
+        } else if line.trim().is_empty() {
+            flush_dep5_stanza(&mut annotations, &mut files, &mut copyrights, &mut license);
+        }
+    }
+
+    Some(annotations)
+}
+
+fn flush_dep5_stanza(
+    annotations: &mut Vec<ReuseAnnotation>,
+    files: &mut Option<String>,
+    copyrights: &mut Vec<String>,
+    license: &mut Option<String>,
+) {
+    if let (Some(path_glob), Some(spdx_expression)) = (files.take(), license.take()) {
+        annotations.push(ReuseAnnotation {
+            path_glob,
+            spdx_expression,
+            copyright_texts: std::mem::take(copyrights),
+            precedence: Precedence::Additional,
+        });
+    } else {
+        copyrights.clear();
+    }
+}
+
+/// The valid char-boundary byte offsets in `s`, in ascending order,
+/// including both `0` and `s.len()`. Slicing `s` at any of these (rather
+/// than at an arbitrary byte index) can never panic on a multi-byte
+/// character.
+fn char_boundaries(s: &str) -> impl Iterator<Item = usize> + '_ {
+    s.char_indices().map(|(i, _)| i).chain(std::iter::once(s.len()))
+}
+
+/// A minimal glob matcher supporting `*` (any run of characters except `/`)
+/// and `**` (any run of characters, including `/`) — enough for the globs
+/// REUSE annotations use in practice.
+fn glob_match(pattern: &str, candidate: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == candidate,
+        Some(idx) => {
+            let (literal, rest) = pattern.split_at(idx);
+            if !candidate.starts_with(literal) {
+                return false;
+            }
+            let remaining = &candidate[literal.len()..];
+
+            if let Some(rest) = rest.strip_prefix("**") {
+                return char_boundaries(remaining).any(|i| glob_match(rest, &remaining[i..]));
+            }
+
+            let rest = &rest[1..]; // skip the single '*'
+            let segment_end = remaining.find('/').unwrap_or(remaining.len());
+            char_boundaries(&remaining[..segment_end]).any(|i| glob_match(rest, &remaining[i..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_match() {
+        assert!(glob_match("src/lib.rs", "src/lib.rs"));
+        assert!(!glob_match("src/lib.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn single_star_matches_within_one_segment() {
+        assert!(glob_match("vendor/*/LICENSE", "vendor/foo/LICENSE"));
+        assert!(!glob_match("vendor/*/LICENSE", "vendor/foo/bar/LICENSE"));
+    }
+
+    #[test]
+    fn double_star_matches_across_segments() {
+        assert!(glob_match("vendor/**/LICENSE", "vendor/foo/bar/LICENSE"));
+        assert!(glob_match("vendor/**/LICENSE", "vendor/foo/LICENSE"));
+    }
+
+    #[test]
+    fn does_not_panic_on_multi_byte_path_components() {
+        // Regression test: iterating byte offsets instead of char
+        // boundaries panicked here with "byte index 4 is not a char
+        // boundary; it is inside 'é'".
+        assert!(glob_match("vendor/*/LICENSE", "vendor/café/LICENSE"));
+        assert!(glob_match("vendor/**/LICENSE", "vendor/café/sub/LICENSE"));
+        assert!(!glob_match("vendor/*/LICENSE", "vendor/café/sub/LICENSE"));
+    }
+
+    #[test]
+    fn matches_checks_the_glob_against_a_relative_path() {
+        let annotation = ReuseAnnotation {
+            path_glob: "vendor/**/LICENSE".to_owned(),
+            spdx_expression: "MIT".to_owned(),
+            copyright_texts: Vec::new(),
+            precedence: Precedence::default(),
+        };
+
+        assert!(annotation.matches(Path::new("vendor/café/LICENSE")));
+        assert!(!annotation.matches(Path::new("src/lib.rs")));
+    }
+
+    /// Writes `contents` to a fresh temp file for the duration of `f`,
+    /// cleaning it up afterwards regardless of the test outcome.
+    fn with_temp_file(name: &str, contents: &str, f: impl FnOnce(&Path)) {
+        let path = std::env::temp_dir().join(format!("cargo-deny-test-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).expect("write temp file");
+        f(&path);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_reuse_toml_parses_annotations() {
+        with_temp_file(
+            "REUSE.toml",
+            r#"
+            [[annotations]]
+            path = "vendor/**/LICENSE"
+            SPDX-FileCopyrightText = "2020 Jane Doe"
+            SPDX-License-Identifier = "MIT"
+            precedence = "override"
+            "#,
+            |path| {
+                let annotations = load_reuse_toml(path).expect("parses");
+                assert_eq!(annotations.len(), 1);
+                assert_eq!(annotations[0].path_glob, "vendor/**/LICENSE");
+                assert_eq!(annotations[0].spdx_expression, "MIT");
+                assert_eq!(annotations[0].copyright_texts, vec!["2020 Jane Doe".to_owned()]);
+                assert_eq!(annotations[0].precedence, Precedence::Override);
+            },
+        );
+    }
+
+    #[test]
+    fn load_reuse_toml_defaults_precedence_to_additional() {
+        with_temp_file(
+            "REUSE-default.toml",
+            r#"
+            [[annotations]]
+            path = "src/lib.rs"
+            SPDX-License-Identifier = "Apache-2.0"
+            "#,
+            |path| {
+                let annotations = load_reuse_toml(path).expect("parses");
+                assert_eq!(annotations[0].precedence, Precedence::Additional);
+            },
+        );
+    }
+
+    #[test]
+    fn load_reuse_toml_returns_none_for_a_missing_file() {
+        assert!(load_reuse_toml(Path::new("/nonexistent/REUSE.toml")).is_none());
+    }
+
+    #[test]
+    fn load_dep5_parses_stanzas_separated_by_blank_lines() {
+        with_temp_file(
+            "dep5",
+            "Files: vendor/openssl/*\nCopyright: 2019 OpenSSL Authors\nLicense: Apache-2.0\n\nFiles: src/*\nCopyright: 2020 Jane Doe\nLicense: MIT\n",
+            |path| {
+                let annotations = load_dep5(path).expect("parses");
+                assert_eq!(annotations.len(), 2);
+
+                assert_eq!(annotations[0].path_glob, "vendor/openssl/*");
+                assert_eq!(annotations[0].spdx_expression, "Apache-2.0");
+                assert_eq!(annotations[0].copyright_texts, vec!["2019 OpenSSL Authors".to_owned()]);
+
+                assert_eq!(annotations[1].path_glob, "src/*");
+                assert_eq!(annotations[1].spdx_expression, "MIT");
+            },
+        );
+    }
+
+    #[test]
+    fn load_dep5_drops_a_stanza_missing_a_license_or_files_field() {
+        with_temp_file(
+            "dep5-incomplete",
+            "Files: src/*\nCopyright: 2020 Jane Doe\n",
+            |path| {
+                let annotations = load_dep5(path).expect("parses");
+                assert!(annotations.is_empty());
+            },
+        );
+    }
+
+    #[test]
+    fn load_prefers_reuse_toml_over_dep5() {
+        let dir = std::env::temp_dir().join(format!("cargo-deny-test-{}-load-prefers", std::process::id()));
+        std::fs::create_dir_all(dir.join(".reuse")).expect("create dirs");
+
+        std::fs::write(
+            dir.join("REUSE.toml"),
+            r#"
+            [[annotations]]
+            path = "src/lib.rs"
+            SPDX-License-Identifier = "MIT"
+            "#,
+        )
+        .expect("write REUSE.toml");
+        std::fs::write(
+            dir.join(".reuse").join("dep5"),
+            "Files: *\nCopyright: 2020 Jane Doe\nLicense: Apache-2.0\n",
+        )
+        .expect("write dep5");
+
+        let annotations = load(&dir);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].spdx_expression, "MIT");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
@@ -0,0 +1,65 @@
+//! The list of valid SPDX license identifiers, used to validate the ids that
+//! show up in a `license` expression.
+//!
+//! This is deliberately independent of [`super::store`]'s corpus: that
+//! corpus only has full license *text* for the handful of licenses common
+//! enough to be worth statistically matching discovered files against, but
+//! a `Cargo.toml` `license` field can legitimately name any registered SPDX
+//! id, text or no text. Tying id validation to the text corpus would reject
+//! perfectly valid, common licenses (`GPL-3.0-only`, `MPL-2.0`, `0BSD`,
+//! `Zlib`, ...) just because we don't ship their full body for file-content
+//! detection.
+//!
+//! This list isn't the complete SPDX license list — that's several hundred
+//! entries long and most of it never shows up on crates.io — but it covers
+//! the ids that do.
+
+const KNOWN_IDS: &[&str] = &[
+    "0BSD",
+    "Apache-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSL-1.0",
+    "CC0-1.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "ISC",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "MIT",
+    "MIT-0",
+    "MPL-2.0",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "Zlib",
+];
+
+/// Whether `id` is a recognized SPDX license identifier.
+pub(crate) fn is_known(id: &str) -> bool {
+    KNOWN_IDS.contains(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_licenses_without_bundled_text() {
+        // These have no entry in `store`'s text corpus, but are still
+        // legitimate SPDX ids that crates.io licenses use in practice.
+        assert!(is_known("GPL-3.0-only"));
+        assert!(is_known("MPL-2.0"));
+        assert!(is_known("0BSD"));
+        assert!(is_known("Zlib"));
+    }
+
+    #[test]
+    fn rejects_unknown_ids() {
+        assert!(!is_known("NotARealLicense-1.0"));
+        assert!(!is_known(""));
+    }
+}